@@ -0,0 +1,219 @@
+use std::fs;
+
+use toml::map::Map;
+use toml::Value;
+
+use crate::{ChosenTimetable, Day, Duration, ParseError};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Weights {
+    pub(crate) total_minutes: f64,
+    pub(crate) wait_minutes: f64,
+    pub(crate) days_with_classes: f64,
+    pub(crate) latest_end: f64,
+    pub(crate) earliest_start: f64,
+}
+
+impl Default for Weights {
+    /// `total_minutes: 1.0` and every other term `0.0`, so that with no
+    /// `[constraints.weights]` table the score reduces to `get_total_duration()`
+    /// alone — matching the solver's previous "rank by total duration" `cmp`.
+    fn default() -> Self {
+        Weights {
+            total_minutes: 1.0,
+            wait_minutes: 0.0,
+            days_with_classes: 0.0,
+            latest_end: 0.0,
+            earliest_start: 0.0,
+        }
+    }
+}
+
+/// Hard blackout windows and soft scoring weights read from the TOML
+/// `[constraints]` table, replacing the old "rank by total duration" rule.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Constraints {
+    pub(crate) blackouts: Vec<(Day, Duration)>,
+    pub(crate) weights: Weights,
+}
+
+impl Constraints {
+    /// True if any chosen shift overlaps a hard blackout window.
+    pub(crate) fn is_blocked(&self, timetable: &ChosenTimetable) -> bool {
+        timetable
+            .0
+            .iter()
+            .filter_map(|(_, shift)| *shift)
+            .any(|shift| {
+                self.blackouts
+                    .iter()
+                    .any(|(day, duration)| shift.day == *day && shift.duration.is_overlapping(duration))
+            })
+    }
+
+    /// Weighted linear score for ranking; lower is better.
+    pub(crate) fn score(&self, timetable: &ChosenTimetable) -> f64 {
+        let w = &self.weights;
+        w.total_minutes * f64::from(timetable.get_total_duration())
+            + w.wait_minutes * f64::from(timetable.get_wait_time_in_minutes_at_day())
+            + w.days_with_classes * (timetable.count_days_with_classes() as f64)
+            + w.latest_end * f64::from(timetable.latest_end_minutes())
+            + w.earliest_start * f64::from(timetable.earliest_start_minutes())
+    }
+}
+
+fn weight(table: &Map<String, Value>, key: &str, default: f64) -> Result<f64, ParseError> {
+    match table.get(key) {
+        None => Ok(default),
+        Some(value) => {
+            let weight = value
+                .as_float()
+                .or_else(|| value.as_integer().map(|i| i as f64))
+                .ok_or(ParseError("Invalid weight value"))?;
+
+            if !weight.is_finite() {
+                return Err(ParseError("Weight must be finite"));
+            }
+
+            Ok(weight)
+        }
+    }
+}
+
+fn parse_weights(table: &Map<String, Value>) -> Result<Weights, ParseError> {
+    let defaults = Weights::default();
+    Ok(Weights {
+        total_minutes: weight(table, "total_minutes", defaults.total_minutes)?,
+        wait_minutes: weight(table, "wait_minutes", defaults.wait_minutes)?,
+        days_with_classes: weight(table, "days_with_classes", defaults.days_with_classes)?,
+        latest_end: weight(table, "latest_end", defaults.latest_end)?,
+        earliest_start: weight(table, "earliest_start", defaults.earliest_start)?,
+    })
+}
+
+fn parse_blackout(value: &Value) -> Result<(Day, Duration), ParseError> {
+    let Value::Table(entry) = value else {
+        return Err(ParseError("Invalid blackout entry"));
+    };
+
+    let day = entry
+        .get("day")
+        .and_then(Value::as_str)
+        .ok_or(ParseError("Blackout entry missing day"))?
+        .parse()?;
+
+    let duration = entry
+        .get("duration")
+        .and_then(Value::as_str)
+        .ok_or(ParseError("Blackout entry missing duration"))?
+        .parse()?;
+
+    Ok((day, duration))
+}
+
+/// Reads the optional `[constraints]` table from `file_name`, falling back
+/// to no blackouts and the default weights (rank by total duration alone,
+/// matching the solver's previous behavior) when it is absent.
+pub(crate) fn load_constraints_file(file_name: &str) -> Result<Constraints, ParseError> {
+    let content = fs::read_to_string(file_name).map_err(|_| ParseError("File not found"))?;
+    let data: Map<String, Value> =
+        toml::from_str(&content).map_err(|_| ParseError("Invalid TOML file"))?;
+
+    let Some(Value::Table(constraints_table)) = data.get("constraints") else {
+        return Ok(Constraints::default());
+    };
+
+    let weights = match constraints_table.get("weights") {
+        Some(Value::Table(weights_table)) => parse_weights(weights_table)?,
+        _ => Weights::default(),
+    };
+
+    let blackouts = match constraints_table.get("blackout") {
+        Some(Value::Array(blackout_entries)) => blackout_entries
+            .iter()
+            .map(parse_blackout)
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => Vec::new(),
+    };
+
+    Ok(Constraints { blackouts, weights })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChosenTimetable, Shift, Subject};
+
+    fn shift(day: Day, start: &str, end: &str) -> Shift {
+        Shift {
+            name: "Lec1".to_string(),
+            day,
+            duration: Duration {
+                start: start.parse().unwrap(),
+                end: end.parse().unwrap(),
+            },
+        }
+    }
+
+    fn subject(name: &str, shift: Shift) -> Subject {
+        Subject {
+            name: name.to_string(),
+            available_shifts: vec![shift],
+            optional: false,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn is_blocked_when_a_shift_overlaps_a_blackout() {
+        let math = subject("Math", shift(Day::Monday, "09:00", "10:00"));
+        let timetable = ChosenTimetable(vec![(&math, Some(&math.available_shifts[0]))]);
+
+        let constraints = Constraints {
+            blackouts: vec![(Day::Monday, shift(Day::Monday, "09:30", "11:00").duration)],
+            weights: Weights::default(),
+        };
+        assert!(constraints.is_blocked(&timetable));
+
+        let constraints = Constraints {
+            blackouts: vec![(Day::Monday, shift(Day::Monday, "10:00", "11:00").duration)],
+            weights: Weights::default(),
+        };
+        assert!(!constraints.is_blocked(&timetable));
+    }
+
+    #[test]
+    fn default_weights_score_by_total_duration_alone() {
+        let math = subject("Math", shift(Day::Monday, "09:00", "10:00"));
+        let physics = subject("Physics", shift(Day::Monday, "10:40", "11:00"));
+        let timetable = ChosenTimetable(vec![
+            (&math, Some(&math.available_shifts[0])),
+            (&physics, Some(&physics.available_shifts[0])),
+        ]);
+
+        let constraints = Constraints::default();
+        assert_eq!(constraints.score(&timetable), f64::from(timetable.get_total_duration()));
+    }
+
+    #[test]
+    fn weight_rejects_nan_and_infinite_values() {
+        let mut table = Map::new();
+        table.insert("total_minutes".to_string(), Value::Float(f64::NAN));
+        assert!(weight(&table, "total_minutes", 1.0).is_err());
+
+        let mut table = Map::new();
+        table.insert("earliest_start".to_string(), Value::Float(f64::INFINITY));
+        assert!(weight(&table, "earliest_start", 0.0).is_err());
+
+        let mut table = Map::new();
+        table.insert("earliest_start".to_string(), Value::Float(f64::NEG_INFINITY));
+        assert!(weight(&table, "earliest_start", 0.0).is_err());
+    }
+
+    #[test]
+    fn parse_weights_rejects_non_finite_weight_table() {
+        let mut weights_table = Map::new();
+        weights_table.insert("wait_minutes".to_string(), Value::Float(f64::NAN));
+        assert!(parse_weights(&weights_table).is_err());
+    }
+}
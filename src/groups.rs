@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::fs;
+
+use toml::map::Map;
+use toml::Value;
+
+use crate::{ChosenTimetable, ParseError, Subject};
+
+/// How many subjects the student must end up enrolled in per alternative
+/// group, read from the TOML `[groups.<name>]` tables.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GroupRules {
+    choose: HashMap<String, usize>,
+}
+
+impl GroupRules {
+    /// True if, for every declared group, exactly the required number of
+    /// that group's subjects were actually included in `timetable`.
+    pub(crate) fn is_satisfied_by(&self, timetable: &ChosenTimetable) -> bool {
+        self.choose.iter().all(|(group, &choose)| {
+            let included = timetable
+                .0
+                .iter()
+                .filter(|(subject, shift)| {
+                    subject.group.as_deref() == Some(group.as_str()) && shift.is_some()
+                })
+                .count();
+            included == choose
+        })
+    }
+
+    /// Warns on stderr about declared groups that can never be satisfied:
+    /// a `choose` count higher than the number of subjects tagged with that
+    /// group, or a group name that matches no subject at all.
+    pub(crate) fn warn_on_unachievable_choices(&self, subjects: &[Subject]) {
+        for warning in self.unachievable_choice_warnings(subjects) {
+            eprintln!("Warning: {warning}");
+        }
+    }
+
+    /// Pure core of [`Self::warn_on_unachievable_choices`], returning the
+    /// messages rather than printing them so the logic can be unit-tested.
+    fn unachievable_choice_warnings(&self, subjects: &[Subject]) -> Vec<String> {
+        self.choose
+            .iter()
+            .filter_map(|(group, &choose)| {
+                let available = subjects
+                    .iter()
+                    .filter(|subject| subject.group.as_deref() == Some(group.as_str()))
+                    .count();
+
+                if available == 0 {
+                    Some(format!("group '{group}' is not tagged on any subject"))
+                } else if choose > available {
+                    Some(format!(
+                        "group '{group}' asks to choose {choose} but only {available} subject(s) are tagged with it"
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reads the optional `[groups.<name>]` tables from `file_name`, each
+/// declaring how many of that group's subjects (marked with `_group =
+/// "<name>"`) the solver must include. Group names tagged on subjects but
+/// with no `[groups.<name>]` table are not constrained; a table without an
+/// integer `choose` is a parse error.
+pub(crate) fn load_group_rules(file_name: &str) -> Result<GroupRules, ParseError> {
+    let content = fs::read_to_string(file_name).map_err(|_| ParseError("File not found"))?;
+    let data: Map<String, Value> =
+        toml::from_str(&content).map_err(|_| ParseError("Invalid TOML file"))?;
+
+    let Some(Value::Table(groups_table)) = data.get("groups") else {
+        return Ok(GroupRules::default());
+    };
+
+    let mut choose = HashMap::new();
+    for (group_name, group_value) in groups_table {
+        let Value::Table(group_value) = group_value else {
+            return Err(ParseError("Invalid group entry"));
+        };
+
+        let count = group_value
+            .get("choose")
+            .and_then(Value::as_integer)
+            .ok_or(ParseError("Group entry missing 'choose'"))?;
+
+        choose.insert(group_name.clone(), count as usize);
+    }
+
+    Ok(GroupRules { choose })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Day, Duration, Hour, Shift};
+    use std::str::FromStr;
+
+    fn elective(name: &str, group: &str) -> Subject {
+        Subject {
+            name: name.to_string(),
+            available_shifts: vec![Shift {
+                name: "Lec1".to_string(),
+                day: Day::Monday,
+                duration: Duration {
+                    start: Hour::from_str("09:00").unwrap(),
+                    end: Hour::from_str("10:00").unwrap(),
+                },
+            }],
+            optional: true,
+            group: Some(group.to_string()),
+        }
+    }
+
+    #[test]
+    fn is_satisfied_by_requires_exact_choose_count() {
+        let a = elective("A", "electives");
+        let b = elective("B", "electives");
+        let c = elective("C", "electives");
+
+        let mut choose = HashMap::new();
+        choose.insert("electives".to_string(), 2);
+        let rules = GroupRules { choose };
+
+        let satisfied = ChosenTimetable(vec![
+            (&a, Some(&a.available_shifts[0])),
+            (&b, Some(&b.available_shifts[0])),
+            (&c, None),
+        ]);
+        assert!(rules.is_satisfied_by(&satisfied));
+
+        let violated = ChosenTimetable(vec![
+            (&a, Some(&a.available_shifts[0])),
+            (&b, None),
+            (&c, None),
+        ]);
+        assert!(!rules.is_satisfied_by(&violated));
+    }
+
+    #[test]
+    fn warns_when_choose_count_exceeds_tagged_subjects() {
+        let a = elective("A", "electives");
+        let b = elective("B", "electives");
+
+        let mut choose = HashMap::new();
+        choose.insert("electives".to_string(), 3);
+        let rules = GroupRules { choose };
+
+        let warnings = rules.unachievable_choice_warnings(&[a, b]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("electives"));
+    }
+
+    #[test]
+    fn no_warning_when_choose_count_is_achievable() {
+        let a = elective("A", "electives");
+        let b = elective("B", "electives");
+
+        let mut choose = HashMap::new();
+        choose.insert("electives".to_string(), 2);
+        let rules = GroupRules { choose };
+
+        assert!(rules.unachievable_choice_warnings(&[a, b]).is_empty());
+    }
+}
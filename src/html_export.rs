@@ -0,0 +1,146 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+
+use itertools::Itertools;
+
+use crate::{ChosenTimetable, Day, Hour};
+
+const SLOT_MINUTES: u16 = 30;
+
+/// Deterministically picks a readable background color for a subject name by
+/// hashing it into a hue and keeping saturation/lightness fixed, so the same
+/// subject always renders the same color across exports.
+fn subject_color(subject_name: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    subject_name.hash(&mut hasher);
+    let hue = hasher.finish() % 360;
+    format!("hsl({hue}, 65%, 80%)")
+}
+
+fn escape_html(field: &str) -> String {
+    field
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn earliest_start_and_latest_end(timetables: &[&ChosenTimetable]) -> Option<(Hour, Hour)> {
+    timetables
+        .iter()
+        .flat_map(|timetable| timetable.0.iter())
+        .filter_map(|(_, shift)| *shift)
+        .map(|shift| shift.duration)
+        .reduce(|acc, duration| acc.merge(&duration))
+        .map(|duration| (duration.start, duration.end))
+}
+
+fn render_timetable(timetable: &ChosenTimetable, axis_start: Hour, axis_end: Hour) -> String {
+    let total_rows = (axis_end.to_minutes() - axis_start.to_minutes()) / SLOT_MINUTES;
+
+    let mut html = String::new();
+    html.push_str(&format!(
+        "<div class=\"calendar\" style=\"grid-template-rows: repeat({}, 1.5em);\">\n",
+        total_rows + 1
+    ));
+
+    for day in Day::DAYS {
+        html.push_str(&format!(
+            "  <div class=\"day-header\" style=\"grid-column: {};\">{:?}</div>\n",
+            Day::DAYS.iter().position(|d| *d == day).unwrap() + 2,
+            day
+        ));
+    }
+
+    for (subject, shift) in &timetable.0 {
+        let Some(shift) = shift else { continue };
+        let column = Day::DAYS.iter().position(|d| *d == shift.day).unwrap() + 2;
+        let row_start = (shift.duration.start.to_minutes() - axis_start.to_minutes()) / SLOT_MINUTES + 2;
+        let row_end = (shift.duration.end.to_minutes() - axis_start.to_minutes()) / SLOT_MINUTES + 2;
+
+        html.push_str(&format!(
+            "  <div class=\"shift\" style=\"grid-column: {column}; grid-row: {row_start} / {row_end}; background-color: {};\">{} {}</div>\n",
+            subject_color(&subject.name),
+            escape_html(&subject.name),
+            escape_html(&shift.name)
+        ));
+    }
+
+    html.push_str("</div>\n");
+    html
+}
+
+/// Renders each given timetable as a CSS-grid week view (columns are
+/// [`Day::DAYS`], rows are fixed half-hour slots spanning the earliest start
+/// to the latest end across all timetables) and writes them as one combined
+/// HTML file at `output_path`.
+pub(crate) fn write_html_calendar(
+    timetables: &[&ChosenTimetable],
+    output_path: &str,
+) -> io::Result<()> {
+    let Some((axis_start, axis_end)) = earliest_start_and_latest_end(timetables) else {
+        return fs::write(output_path, "<html><body>No timetables to display.</body></html>");
+    };
+
+    let grids = timetables
+        .iter()
+        .enumerate()
+        .map(|(i, timetable)| {
+            format!(
+                "<h2>Option {}</h2>\n{}",
+                i + 1,
+                render_timetable(timetable, axis_start, axis_end)
+            )
+        })
+        .join("\n");
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n\
+         .calendar {{ display: grid; grid-template-columns: 4em repeat(5, 1fr); gap: 1px; margin-bottom: 2em; }}\n\
+         .day-header {{ font-weight: bold; text-align: center; grid-row: 1; }}\n\
+         .shift {{ border-radius: 4px; padding: 2px 4px; font-size: 0.8em; overflow: hidden; }}\n\
+         </style>\n</head>\n<body>\n{grids}\n</body>\n</html>\n"
+    );
+
+    fs::write(output_path, html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Duration, Shift, Subject};
+
+    #[test]
+    fn escape_html_escapes_reserved_characters() {
+        assert_eq!(
+            escape_html("<script>alert('x')</script> & \"quoted\""),
+            "&lt;script&gt;alert('x')&lt;/script&gt; &amp; &quot;quoted&quot;"
+        );
+    }
+
+    #[test]
+    fn render_timetable_escapes_subject_and_shift_names() {
+        let subject = Subject {
+            name: "<script>alert(1)</script>".to_string(),
+            available_shifts: vec![Shift {
+                name: "T1 & <Lab>".to_string(),
+                day: Day::Monday,
+                duration: Duration {
+                    start: "09:00".parse().unwrap(),
+                    end: "10:00".parse().unwrap(),
+                },
+            }],
+            optional: false,
+            group: None,
+        };
+        let timetable = ChosenTimetable(vec![(&subject, Some(&subject.available_shifts[0]))]);
+
+        let html = render_timetable(&timetable, "09:00".parse().unwrap(), "11:00".parse().unwrap());
+
+        assert!(!html.contains("<script>alert(1)</script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("T1 &amp; &lt;Lab&gt;"));
+    }
+}
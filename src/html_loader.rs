@@ -0,0 +1,206 @@
+use std::fs;
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::schedule_source::ScheduleSource;
+use crate::{Day, Duration, ParseError, Shift, Subject};
+
+const USER_AGENT: &str = "timetable-solver/0.1 (+https://github.com/chicoferreira/timetable-solver)";
+
+/// Loads subjects and shifts by scraping an HTML timetable page instead of
+/// requiring a hand-written `schedule.toml`. Expects the page to contain a
+/// `<table>` whose header row names a `Day` per column and whose remaining
+/// rows start with a `<start>-><end>` time cell, followed by one cell per
+/// day holding `"<subject><br><shift>"` (a literal newline also works;
+/// blank cells mean no shift that slot).
+pub(crate) struct HtmlSource<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) cache: Option<&'a str>,
+}
+
+impl ScheduleSource for HtmlSource<'_> {
+    fn load(&self) -> Result<Vec<Subject>, ParseError> {
+        let html = self.fetch_html()?;
+        parse_timetable_html(&html)
+    }
+}
+
+impl HtmlSource<'_> {
+    fn fetch_html(&self) -> Result<String, ParseError> {
+        if let Some(cache_path) = self.cache {
+            if let Ok(cached) = fs::read_to_string(cache_path) {
+                return Ok(cached);
+            }
+        }
+
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|_| ParseError("Failed to build HTTP client"))?;
+
+        let html = client
+            .get(self.url)
+            .send()
+            .and_then(|response| response.text())
+            .map_err(|_| ParseError("Failed to fetch timetable page"))?;
+
+        if let Some(cache_path) = self.cache {
+            let _ = fs::write(cache_path, &html);
+        }
+
+        Ok(html)
+    }
+}
+
+/// Collects a cell's text the way `ElementRef::text()` does, except a
+/// `<br>` is treated as a newline boundary rather than being silently
+/// dropped, since pages commonly separate a subject and shift name with
+/// `<br>` rather than a literal newline character.
+fn cell_text(cell: ElementRef) -> String {
+    let mut text = String::new();
+    for node in cell.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(element) if element.name() == "br" => text.push('\n'),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn parse_timetable_html(html: &str) -> Result<Vec<Subject>, ParseError> {
+    let document = Html::parse_document(html);
+    let table_selector = Selector::parse("table").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let cell_selector = Selector::parse("th, td").unwrap();
+
+    let table = document
+        .select(&table_selector)
+        .next()
+        .ok_or(ParseError("No table found in timetable page"))?;
+
+    let mut rows = table.select(&row_selector);
+
+    let header_row = rows
+        .next()
+        .ok_or(ParseError("Timetable page has no header row"))?;
+    let header_cells: Vec<_> = header_row.select(&cell_selector).collect();
+    let day_columns: Vec<Option<Day>> = header_cells
+        .split_first()
+        .map(|(_, day_cells)| day_cells)
+        .unwrap_or(&[])
+        .iter()
+        .map(|cell| cell.text().collect::<String>().trim().parse().ok())
+        .collect();
+
+    let mut subjects: Vec<Subject> = Vec::new();
+
+    for row in rows {
+        let cells: Vec<_> = row.select(&cell_selector).collect();
+        let Some((time_cell, shift_cells)) = cells.split_first() else {
+            continue;
+        };
+
+        let Ok(duration) = time_cell.text().collect::<String>().trim().parse::<Duration>() else {
+            continue;
+        };
+
+        for (day, cell) in day_columns.iter().zip(shift_cells) {
+            let Some(day) = day else { continue };
+
+            let text = cell_text(*cell);
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let mut segments = text.split('\n').map(str::trim);
+            let subject_name = segments.next().unwrap_or(text);
+            let shift_name = segments
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let shift_name = if shift_name.is_empty() {
+                "Shift".to_string()
+            } else {
+                shift_name
+            };
+
+            let shift = Shift {
+                name: shift_name,
+                day: *day,
+                duration,
+            };
+
+            match subjects.iter_mut().find(|subject| subject.name == subject_name) {
+                Some(subject) => subject.available_shifts.push(shift),
+                None => subjects.push(Subject {
+                    name: subject_name.to_string(),
+                    available_shifts: vec![shift],
+                    optional: false,
+                    group: None,
+                }),
+            }
+        }
+    }
+
+    Ok(subjects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_day_columns_with_data_row_cells() {
+        let html = "\
+            <table>\
+              <tr><th></th><th>Monday</th><th>Tuesday</th></tr>\
+              <tr><td>09:00->10:00</td><td>Math\nLec1</td><td>Physics\nLec1</td></tr>\
+            </table>";
+
+        let subjects = parse_timetable_html(html).unwrap();
+
+        let math = subjects.iter().find(|subject| subject.name == "Math").unwrap();
+        assert_eq!(math.available_shifts[0].day, Day::Monday);
+
+        let physics = subjects
+            .iter()
+            .find(|subject| subject.name == "Physics")
+            .unwrap();
+        assert_eq!(physics.available_shifts[0].day, Day::Tuesday);
+    }
+
+    #[test]
+    fn splits_subject_and_shift_on_a_br_tag() {
+        let html = "\
+            <table>\
+              <tr><th></th><th>Monday</th></tr>\
+              <tr><td>09:00->10:00</td><td>Math<br>Lec1</td></tr>\
+            </table>";
+
+        let subjects = parse_timetable_html(html).unwrap();
+
+        let math = subjects.iter().find(|subject| subject.name == "Math").unwrap();
+        assert_eq!(math.available_shifts[0].name, "Lec1");
+        assert_eq!(math.available_shifts[0].day, Day::Monday);
+    }
+
+    #[test]
+    fn joins_multiple_br_segments_into_the_shift_name() {
+        let html = "\
+            <table>\
+              <tr><th></th><th>Monday</th></tr>\
+              <tr><td>09:00->10:00</td><td>Biology<br>Lab<br>Room 5</td></tr>\
+            </table>";
+
+        let subjects = parse_timetable_html(html).unwrap();
+
+        let biology = subjects
+            .iter()
+            .find(|subject| subject.name == "Biology")
+            .unwrap();
+        assert_eq!(biology.available_shifts[0].name, "Lab Room 5");
+        assert!(!biology.available_shifts[0].name.contains('\n'));
+    }
+}
@@ -0,0 +1,233 @@
+use std::fs;
+
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Weekday};
+
+use crate::{ChosenTimetable, Day, Hour};
+
+/// RFC 5545 §3.1 caps content lines at 75 octets; longer lines must be
+/// folded with a CRLF followed by a single leading space.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Typical semester length, used to bound each `RRULE` with an `UNTIL` so
+/// imported events don't recur forever (there's no per-subject end date to
+/// read from `schedule.toml`).
+const SEMESTER_WEEKS: i64 = 15;
+
+fn day_to_weekday(day: Day) -> Weekday {
+    match day {
+        Day::Monday => Weekday::Mon,
+        Day::Tuesday => Weekday::Tue,
+        Day::Wednesday => Weekday::Wed,
+        Day::Thursday => Weekday::Thu,
+        Day::Friday => Weekday::Fri,
+    }
+}
+
+fn day_to_byday(day: Day) -> &'static str {
+    match day {
+        Day::Monday => "MO",
+        Day::Tuesday => "TU",
+        Day::Wednesday => "WE",
+        Day::Thursday => "TH",
+        Day::Friday => "FR",
+    }
+}
+
+/// First date on/after `semester_start` that falls on `day`.
+fn first_occurrence(semester_start: NaiveDate, day: Day) -> NaiveDate {
+    let target = day_to_weekday(day);
+    let mut date = semester_start;
+    while date.weekday() != target {
+        date += ChronoDuration::days(1);
+    }
+    date
+}
+
+fn format_local_datetime(date: NaiveDate, hour: Hour) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}00",
+        date.year(),
+        date.month(),
+        date.day(),
+        hour.hour(),
+        hour.minute()
+    )
+}
+
+/// End-of-day timestamp for the last week the `RRULE` should recur on, so the
+/// final occurrence is kept regardless of the shift's own start/end time.
+fn format_until(date: NaiveDate) -> String {
+    format!("{:04}{:02}{:02}T235959", date.year(), date.month(), date.day())
+}
+
+/// Backslash-escapes the characters RFC 5545 §3.3.11 reserves in TEXT
+/// values (`\`, `,`, `;`, newlines) so subject/shift names can't corrupt the
+/// property or split it across unfolded physical lines.
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+fn fold_line(line: &str) -> String {
+    if line.len() <= MAX_LINE_OCTETS {
+        return line.to_string();
+    }
+
+    let mut folded = String::new();
+    let mut start = 0;
+    let mut first = true;
+    while start < line.len() {
+        let budget = if first { MAX_LINE_OCTETS } else { MAX_LINE_OCTETS - 1 };
+        let mut end = (start + budget).min(line.len());
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        if !first {
+            folded.push_str("\r\n ");
+        }
+        folded.push_str(&line[start..end]);
+        start = end;
+        first = false;
+    }
+    folded
+}
+
+/// Writes `timetable` as a VCALENDAR file anchored to `semester_start`
+/// (`YYYY-MM-DD`), with one weekly-recurring `VEVENT` per chosen shift,
+/// recurring for [`SEMESTER_WEEKS`] weeks before stopping.
+pub(crate) fn write_ics(
+    timetable: &ChosenTimetable,
+    semester_start: &str,
+    output_path: &str,
+) -> Result<(), String> {
+    let semester_start = NaiveDate::parse_from_str(semester_start, "%Y-%m-%d")
+        .map_err(|_| format!("invalid semester start date '{semester_start}', expected YYYY-MM-DD"))?;
+
+    let mut ics = String::new();
+    ics.push_str("BEGIN:VCALENDAR\r\n");
+    ics.push_str("VERSION:2.0\r\n");
+    ics.push_str("PRODID:-//timetable-solver//EN\r\n");
+
+    for (i, (subject, shift)) in timetable.0.iter().enumerate() {
+        let Some(shift) = shift else { continue };
+        let event_date = first_occurrence(semester_start, shift.day);
+        let dtstart = format_local_datetime(event_date, shift.duration.start);
+        let dtend = format_local_datetime(event_date, shift.duration.end);
+        let until = format_until(event_date + ChronoDuration::days(7 * (SEMESTER_WEEKS - 1)));
+        let uid = format!("{i}-{}-{}@timetable-solver", subject.name, shift.name).replace(' ', "-");
+        let uid = escape_text(&uid);
+
+        for line in [
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{uid}"),
+            format!("DTSTART:{dtstart}"),
+            format!("DTEND:{dtend}"),
+            format!(
+                "RRULE:FREQ=WEEKLY;UNTIL={until};BYDAY={}",
+                day_to_byday(shift.day)
+            ),
+            format!("SUMMARY:{} {}", escape_text(&subject.name), escape_text(&shift.name)),
+            "END:VEVENT".to_string(),
+        ] {
+            ics.push_str(&fold_line(&line));
+            ics.push_str("\r\n");
+        }
+    }
+
+    ics.push_str("END:VCALENDAR\r\n");
+
+    fs::write(output_path, ics).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_text_escapes_backslash_comma_and_semicolon() {
+        assert_eq!(escape_text(r"a\b,c;d"), r"a\\b\,c\;d");
+        assert_eq!(escape_text("no special chars"), "no special chars");
+    }
+
+    #[test]
+    fn escape_text_escapes_embedded_newlines() {
+        assert_eq!(escape_text("Lab\nRoom 5"), "Lab\\nRoom 5");
+        assert_eq!(escape_text("Lab\r\nRoom 5"), "Lab\\nRoom 5");
+    }
+
+    #[test]
+    fn fold_line_leaves_short_lines_untouched() {
+        let line = "SUMMARY:Short line";
+        assert_eq!(fold_line(line), line);
+    }
+
+    #[test]
+    fn fold_line_wraps_at_75_octets_with_a_leading_space_continuation() {
+        let line = "SUMMARY:".to_string() + &"x".repeat(100);
+        let folded = fold_line(&line);
+
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), MAX_LINE_OCTETS);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(parts[0].to_string() + &parts[1][1..], line);
+    }
+
+    #[test]
+    fn first_occurrence_returns_same_date_when_already_on_target_day() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        assert_eq!(monday.weekday(), Weekday::Mon);
+
+        assert_eq!(first_occurrence(monday, Day::Monday), monday);
+    }
+
+    #[test]
+    fn first_occurrence_advances_to_the_next_matching_weekday() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2026, 3, 6).unwrap();
+
+        assert_eq!(first_occurrence(monday, Day::Friday), friday);
+    }
+
+    #[test]
+    fn write_ics_bounds_the_rrule_with_an_until_within_the_semester() {
+        let math = crate::Subject {
+            name: "Math".to_string(),
+            available_shifts: vec![crate::Shift {
+                name: "Lec1".to_string(),
+                day: Day::Monday,
+                duration: crate::Duration {
+                    start: "09:00".parse().unwrap(),
+                    end: "10:00".parse().unwrap(),
+                },
+            }],
+            optional: false,
+            group: None,
+        };
+        let timetable = ChosenTimetable(vec![(&math, Some(&math.available_shifts[0]))]);
+
+        let output_path = std::env::temp_dir().join(format!("ics_export_test_{}.ics", std::process::id()));
+        let output_path = output_path.to_str().unwrap();
+
+        write_ics(&timetable, "2026-03-02", output_path).unwrap();
+        let contents = fs::read_to_string(output_path).unwrap();
+        fs::remove_file(output_path).ok();
+
+        let rrule_line = contents.lines().find(|line| line.starts_with("RRULE:")).unwrap();
+        assert!(rrule_line.contains("UNTIL="));
+        assert!(!rrule_line.contains("UNTIL=;"));
+
+        let until = rrule_line
+            .split("UNTIL=")
+            .nth(1)
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap();
+        assert_eq!(until, format_until(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap() + ChronoDuration::days(7 * (SEMESTER_WEEKS - 1))));
+    }
+}
@@ -1,4 +1,3 @@
-use std::cmp::Ordering;
 use std::fs;
 use std::str::FromStr;
 use std::time::Instant;
@@ -7,8 +6,20 @@ use itertools::Itertools;
 use toml::map::Map;
 use toml::Value;
 
+mod constraints;
+mod groups;
+mod html_export;
+mod html_loader;
+mod ics_export;
+mod schedule_source;
+mod table_output;
+
+use constraints::Constraints;
+use groups::GroupRules;
+use table_output::OutputFormat;
+
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
-enum Day {
+pub(crate) enum Day {
     Monday,
     Tuesday,
     Wednesday,
@@ -26,7 +37,8 @@ impl Day {
     ];
 }
 
-struct ParseError(&'static str);
+#[derive(Debug)]
+pub(crate) struct ParseError(&'static str);
 
 impl FromStr for Day {
     type Err = ParseError;
@@ -43,15 +55,25 @@ impl FromStr for Day {
     }
 }
 
+/// A time of day, normalized to total minutes since midnight so every
+/// comparison and merge operates on a single scalar instead of drifting
+/// hour/minute fields independently.
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
-struct Hour {
-    hour: u16,
-    minute: u16,
+pub(crate) struct Hour {
+    minutes: u16,
 }
 
 impl Hour {
-    fn to_minutes(self) -> u16 {
-        self.hour * 60 + self.minute
+    pub(crate) fn to_minutes(self) -> u16 {
+        self.minutes
+    }
+
+    pub(crate) fn hour(self) -> u16 {
+        self.minutes / 60
+    }
+
+    pub(crate) fn minute(self) -> u16 {
+        self.minutes % 60
     }
 }
 
@@ -61,17 +83,26 @@ impl FromStr for Hour {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (hour, minute) = s.split(':').collect_tuple().unwrap_or((s, "0"));
 
-        let hour = hour.parse().map_err(|_| ParseError("Invalid hour"))?;
-        let minute = minute.parse().map_err(|_| ParseError("Invalid minute"))?;
+        let hour: u16 = hour.parse().map_err(|_| ParseError("Invalid hour"))?;
+        let minute: u16 = minute.parse().map_err(|_| ParseError("Invalid minute"))?;
 
-        Ok(Hour { hour, minute })
+        if hour >= 24 {
+            return Err(ParseError("Hour must be less than 24"));
+        }
+        if minute >= 60 {
+            return Err(ParseError("Minute must be less than 60"));
+        }
+
+        Ok(Hour {
+            minutes: hour * 60 + minute,
+        })
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
-struct Duration {
-    start: Hour,
-    end: Hour,
+pub(crate) struct Duration {
+    pub(crate) start: Hour,
+    pub(crate) end: Hour,
 }
 
 impl Duration {
@@ -79,20 +110,22 @@ impl Duration {
         self.end.to_minutes() - self.start.to_minutes()
     }
 
-    fn merge(&self, duration: &Duration) -> Duration {
-        Duration {
-            start: Hour {
-                hour: self.start.hour.min(duration.start.hour),
-                minute: self.start.minute.min(duration.start.minute),
-            },
-            end: Hour {
-                hour: self.end.hour.max(duration.end.hour),
-                minute: self.end.minute.max(duration.end.minute),
-            },
-        }
+    pub(crate) fn merge(&self, duration: &Duration) -> Duration {
+        let start = if self.start.to_minutes() <= duration.start.to_minutes() {
+            self.start
+        } else {
+            duration.start
+        };
+        let end = if self.end.to_minutes() >= duration.end.to_minutes() {
+            self.end
+        } else {
+            duration.end
+        };
+
+        Duration { start, end }
     }
 
-    fn is_overlapping(&self, duration: &Duration) -> bool {
+    pub(crate) fn is_overlapping(&self, duration: &Duration) -> bool {
         self.start.to_minutes() < duration.end.to_minutes()
             && self.end.to_minutes() > duration.start.to_minutes()
     }
@@ -115,10 +148,10 @@ impl FromStr for Duration {
 }
 
 #[derive(Debug, Eq, PartialEq, Hash)]
-struct Shift {
-    name: String,
-    day: Day,
-    duration: Duration,
+pub(crate) struct Shift {
+    pub(crate) name: String,
+    pub(crate) day: Day,
+    pub(crate) duration: Duration,
 }
 
 impl Shift {
@@ -128,49 +161,57 @@ impl Shift {
 }
 
 #[derive(Debug, Eq, PartialEq, Hash)]
-struct Subject {
-    name: String,
-    available_shifts: Vec<Shift>,
+pub(crate) struct Subject {
+    pub(crate) name: String,
+    pub(crate) available_shifts: Vec<Shift>,
+    /// If true, the solver may pick none of this subject's shifts.
+    pub(crate) optional: bool,
+    /// Alternative-group name; the solver enforces the group's "choose N"
+    /// rule across every subject sharing the same group name.
+    pub(crate) group: Option<String>,
 }
 
 #[derive(Debug)]
-struct ChosenTimetable<'a>(Vec<(&'a Subject, &'a Shift)>);
+/// A subject is paired with `None` when it's optional/grouped and the
+/// solver chose to not enroll in any of its shifts.
+pub(crate) struct ChosenTimetable<'a>(pub(crate) Vec<(&'a Subject, Option<&'a Shift>)>);
 
 impl<'a> ChosenTimetable<'a> {
     fn prettify(&self) -> String {
         self.0
             .iter()
-            .map(|(subject, shift)| format!("{} {}", subject.name, shift.name))
+            .map(|(subject, shift)| match shift {
+                Some(shift) => format!("{} {}", subject.name, shift.name),
+                None => format!("{} (not taken)", subject.name),
+            })
             .join(", ")
     }
 }
 
 impl<'a> ChosenTimetable<'a> {
+    fn chosen_shifts(&self) -> impl Iterator<Item = &'a Shift> + Clone + '_ {
+        self.0.iter().filter_map(|(_, shift)| *shift)
+    }
+
     fn get_total_duration_at_day(&self, day: Day) -> Option<Duration> {
-        self.0
-            .iter()
-            .map(|(_, shift)| shift)
+        self.chosen_shifts()
             .filter(|shift| shift.day == day)
             .map(|shift| shift.duration)
             .reduce(|duration, next_duration| duration.merge(&next_duration))
     }
 
     fn get_minutes_in_classes(&self) -> u16 {
-        self.0
-            .iter()
-            .map(|(_, shift)| shift)
-            .map(|shift| shift.duration.minutes())
-            .sum()
+        self.chosen_shifts().map(|shift| shift.duration.minutes()).sum()
     }
 
-    fn get_wait_time_in_minutes_at_day(&self) -> u16 {
+    pub(crate) fn get_wait_time_in_minutes_at_day(&self) -> u16 {
         let total_duration = self.get_total_duration();
         let minutes_in_classes = self.get_minutes_in_classes();
 
         total_duration - minutes_in_classes
     }
 
-    fn get_total_duration(&self) -> u16 {
+    pub(crate) fn get_total_duration(&self) -> u16 {
         Day::DAYS
             .iter()
             .filter_map(|day| self.get_total_duration_at_day(*day))
@@ -182,82 +223,147 @@ impl<'a> ChosenTimetable<'a> {
         self.get_total_duration_at_day(day).is_some()
     }
 
-    fn count_days_with_classes(&self) -> usize {
+    pub(crate) fn count_days_with_classes(&self) -> usize {
         Day::DAYS
             .iter()
             .filter(|day| self.has_classes_at_day(**day))
             .count()
     }
 
+    pub(crate) fn latest_end_minutes(&self) -> u16 {
+        self.chosen_shifts()
+            .map(|shift| shift.duration.end.to_minutes())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn earliest_start_minutes(&self) -> u16 {
+        self.chosen_shifts()
+            .map(|shift| shift.duration.start.to_minutes())
+            .min()
+            .unwrap_or(0)
+    }
+
     fn is_overlapping(&self) -> bool {
-        self.0
-            .iter()
-            .map(|(_, shift)| shift)
+        self.chosen_shifts()
             .tuple_combinations()
             .any(|(shift1, shift2)| shift1.is_overlapping(shift2))
     }
+}
 
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.get_total_duration().cmp(&other.get_total_duration())
-    }
+fn best_timetables_with_day_count<'a, 'b>(
+    results: &'b [ChosenTimetable<'a>],
+    days: usize,
+    constraints: &Constraints,
+) -> Vec<&'b ChosenTimetable<'a>> {
+    results
+        .iter()
+        .filter(|timetable| timetable.count_days_with_classes() == days)
+        .min_set_by(|a, b| {
+            constraints
+                .score(a)
+                .partial_cmp(&constraints.score(b))
+                .unwrap()
+        })
 }
 
-fn solve(subjects: Vec<Subject>) {
+fn get_hours_at_day(result: &ChosenTimetable, day: Day) -> u16 {
+    result
+        .get_total_duration_at_day(day)
+        .map(|duration| duration.minutes())
+        .unwrap_or(0)
+        / 60
+}
+
+fn build_rows(results: &[&ChosenTimetable]) -> Vec<table_output::ResultRow> {
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| table_output::ResultRow {
+            rank: i + 1,
+            subjects: result.prettify(),
+            total_hours: result.get_total_duration() / 60,
+            hours_per_day: Day::DAYS
+                .iter()
+                .map(|day| get_hours_at_day(result, *day))
+                .join("+"),
+            wait_hours: result.get_wait_time_in_minutes_at_day() / 60,
+        })
+        .collect()
+}
+
+fn print_results(results: &[&ChosenTimetable], format: table_output::OutputFormat) {
+    print!("{}", table_output::render(&build_rows(results), format));
+}
+
+fn solve(subjects: Vec<Subject>, constraints: &Constraints, group_rules: &GroupRules, cli: &CliOptions) {
     let result = subjects
         .iter()
         .map(|subject| {
-            subject
-                .available_shifts
-                .iter()
-                .map(move |shift| (subject, shift))
+            let skippable = subject.optional || subject.group.is_some();
+            let choices: Vec<Option<&Shift>> = if skippable {
+                subject.available_shifts.iter().map(Some).chain([None]).collect()
+            } else {
+                subject.available_shifts.iter().map(Some).collect()
+            };
+
+            choices.into_iter().map(move |shift| (subject, shift))
         })
         .multi_cartesian_product();
 
     let result: Vec<ChosenTimetable> = result
         .map(|combination| ChosenTimetable(combination.to_vec()))
+        .filter(|timetable| group_rules.is_satisfied_by(timetable))
         .filter(|timetable| !timetable.is_overlapping())
+        .filter(|timetable| !constraints.is_blocked(timetable))
         .collect();
 
     println!("Total possible timetables: {}", result.len());
 
-    fn generate_results(results: &[ChosenTimetable], days: usize) {
-        let results = results
-            .iter()
-            .filter(|timetable| timetable.count_days_with_classes() == days)
-            .min_set_by(|a, b| a.cmp(b));
-
-        for (i, result) in (1..).zip(results) {
-            fn get_hours_at_day(result: &ChosenTimetable, day: Day) -> u16 {
-                result
-                    .get_total_duration_at_day(day)
-                    .map(|duration| duration.minutes())
-                    .unwrap_or(0)
-                    / 60
-            }
-
-            println!(
-                "{}. {:?} - {} hours ({}) with {} wait hours",
-                i,
-                result.prettify(),
-                result.get_total_duration() / 60,
-                Day::DAYS
-                    .iter()
-                    .map(|day| get_hours_at_day(result, *day))
-                    .join("+"),
-                result.get_wait_time_in_minutes_at_day() / 60
-            );
-        }
-    }
+    let mut best_overall: Vec<&ChosenTimetable> = Vec::new();
     (1..=5).for_each(|days| {
         println!();
         println!("Best timetables with {} days of classes:", days);
-        generate_results(&result, days);
+        let best = best_timetables_with_day_count(&result, days, constraints);
+        print_results(&best, cli.format);
+        best_overall.extend(best);
     });
+
+    if let Some(html_output) = &cli.html_output {
+        if let Err(err) = html_export::write_html_calendar(&best_overall, html_output) {
+            eprintln!("Failed to write HTML export to {}: {}", html_output, err);
+        } else {
+            println!("\nWrote HTML calendar export to {}", html_output);
+        }
+    }
+
+    if let Some(ics_output) = &cli.ics_output {
+        let index = cli.ics_select.unwrap_or(1);
+        match best_overall.get(index.saturating_sub(1)) {
+            None => eprintln!("No ranked timetable at index {} to export", index),
+            Some(timetable) => {
+                let Some(semester_start) = &cli.ics_semester_start else {
+                    eprintln!("--ics-start <YYYY-MM-DD> is required when exporting with --ics");
+                    return;
+                };
+                match ics_export::write_ics(timetable, semester_start, ics_output) {
+                    Ok(()) => println!("Wrote ICS export to {}", ics_output),
+                    Err(err) => eprintln!("Failed to write ICS export: {}", err),
+                }
+            }
+        }
+    }
 }
 
-fn handle_shifts_array(shift_table: Map<String, Value>) -> Result<Vec<Shift>, ParseError> {
+/// Keys reserved for subject metadata rather than shift definitions.
+const RESERVED_SUBJECT_KEYS: [&str; 2] = ["_optional", "_group"];
+
+fn handle_shifts_array(shift_table: &Map<String, Value>) -> Result<Vec<Shift>, ParseError> {
     let mut shifts = Vec::new();
     for (shift_name, shift_data) in shift_table {
+        if RESERVED_SUBJECT_KEYS.contains(&shift_name.as_str()) {
+            continue;
+        }
         if let Value::String(shift_data) = shift_data {
             let (day, duration) =
                 shift_data
@@ -279,7 +385,7 @@ fn handle_shifts_array(shift_table: Map<String, Value>) -> Result<Vec<Shift>, Pa
     Ok(shifts)
 }
 
-fn load_schedule_file(file_name: &str) -> Result<Vec<Subject>, ParseError> {
+pub(crate) fn load_schedule_file(file_name: &str) -> Result<Vec<Subject>, ParseError> {
     let content = fs::read_to_string(file_name).map_err(|_| ParseError("File not found"))?;
     let data: Map<String, Value> =
         toml::from_str(&content).map_err(|_| ParseError("Invalid TOML file"))?;
@@ -290,10 +396,23 @@ fn load_schedule_file(file_name: &str) -> Result<Vec<Subject>, ParseError> {
         if let Value::Array(shifts_tables) = shifts_tables {
             for shift_table in shifts_tables {
                 if let Value::Table(shift_table) = shift_table {
-                    let shifts = handle_shifts_array(shift_table)?;
+                    let shifts = handle_shifts_array(&shift_table)?;
+                    // "_optional"/"_group" are reserved keys read as subject
+                    // metadata rather than shift definitions.
+                    let optional = shift_table
+                        .get("_optional")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    let group = shift_table
+                        .get("_group")
+                        .and_then(Value::as_str)
+                        .map(String::from);
+
                     result.push(Subject {
                         name: subject_name.clone(),
                         available_shifts: shifts,
+                        optional,
+                        group,
                     });
                 }
             }
@@ -303,13 +422,129 @@ fn load_schedule_file(file_name: &str) -> Result<Vec<Subject>, ParseError> {
     Ok(result)
 }
 
+struct CliOptions {
+    html_output: Option<String>,
+    ics_output: Option<String>,
+    ics_semester_start: Option<String>,
+    ics_select: Option<usize>,
+    schedule_url: Option<String>,
+    schedule_cache: Option<String>,
+    format: OutputFormat,
+}
+
+fn parse_cli_options() -> CliOptions {
+    let mut html_output = None;
+    let mut ics_output = None;
+    let mut ics_semester_start = None;
+    let mut ics_select = None;
+    let mut schedule_url = None;
+    let mut schedule_cache = None;
+    let mut format = OutputFormat::Plain;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => html_output = args.next(),
+            "--ics" => ics_output = args.next(),
+            "--ics-start" => ics_semester_start = args.next(),
+            "--ics-select" => ics_select = args.next().and_then(|s| s.parse().ok()),
+            "--schedule-url" => schedule_url = args.next(),
+            "--cache" => schedule_cache = args.next(),
+            "--format" => {
+                if let Some(value) = args.next() {
+                    match value.parse::<OutputFormat>() {
+                        Ok(parsed) => format = parsed,
+                        Err(err) => eprintln!("Ignoring --format {}: {}", value, err.0),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    CliOptions {
+        html_output,
+        ics_output,
+        ics_semester_start,
+        ics_select,
+        schedule_url,
+        schedule_cache,
+        format,
+    }
+}
+
 fn main() {
-    let vec = load_schedule_file("schedule.toml").unwrap_or_else(|err| {
-        eprintln!("Error parsing schedule file: {}", err.0);
+    let cli = parse_cli_options();
+
+    let vec = load_schedule(&cli).unwrap_or_else(|err| {
+        eprintln!("Error loading schedule: {}", err.0);
         std::process::exit(1);
     });
 
+    let constraints = if cli.schedule_url.is_some() {
+        Constraints::default()
+    } else {
+        constraints::load_constraints_file("schedule.toml").unwrap_or_else(|err| {
+            eprintln!("Error parsing constraints: {}", err.0);
+            Constraints::default()
+        })
+    };
+
+    let group_rules = if cli.schedule_url.is_some() {
+        GroupRules::default()
+    } else {
+        groups::load_group_rules("schedule.toml").unwrap_or_else(|err| {
+            eprintln!("Error parsing groups: {}", err.0);
+            GroupRules::default()
+        })
+    };
+    group_rules.warn_on_unachievable_choices(&vec);
+
     let before = Instant::now();
-    solve(vec);
+    solve(vec, &constraints, &group_rules, &cli);
     println!("Elapsed time: {:.2?}", before.elapsed());
 }
+
+fn load_schedule(cli: &CliOptions) -> Result<Vec<Subject>, ParseError> {
+    use schedule_source::ScheduleSource;
+
+    match &cli.schedule_url {
+        Some(url) => html_loader::HtmlSource {
+            url,
+            cache: cli.schedule_cache.as_deref(),
+        }
+        .load(),
+        None => schedule_source::TomlSource {
+            file_name: "schedule.toml",
+        }
+        .load(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_hour_past_midnight() {
+        assert!("24:00".parse::<Hour>().is_err());
+        assert!("25:30".parse::<Hour>().is_err());
+    }
+
+    #[test]
+    fn rejects_minute_overflow() {
+        assert!("10:60".parse::<Hour>().is_err());
+        assert!("10:99".parse::<Hour>().is_err());
+    }
+
+    #[test]
+    fn merges_non_contiguous_shifts_into_true_span() {
+        let morning: Duration = "09:40->10:30".parse().unwrap();
+        let afternoon: Duration = "13:05->14:00".parse().unwrap();
+
+        let merged = morning.merge(&afternoon);
+
+        assert_eq!(merged.start, "09:40".parse::<Hour>().unwrap());
+        assert_eq!(merged.end, "14:00".parse::<Hour>().unwrap());
+    }
+}
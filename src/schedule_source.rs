@@ -0,0 +1,17 @@
+use crate::{load_schedule_file, ParseError, Subject};
+
+/// A source of `Subject` data, so `solve` doesn't care whether the schedule
+/// came from a hand-written TOML file or was scraped from a timetable page.
+pub(crate) trait ScheduleSource {
+    fn load(&self) -> Result<Vec<Subject>, ParseError>;
+}
+
+pub(crate) struct TomlSource<'a> {
+    pub(crate) file_name: &'a str,
+}
+
+impl ScheduleSource for TomlSource<'_> {
+    fn load(&self) -> Result<Vec<Subject>, ParseError> {
+        load_schedule_file(self.file_name)
+    }
+}
@@ -0,0 +1,197 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Plain,
+    Markdown,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(ParseError(
+                "Invalid output format. Expected: plain, markdown, csv",
+            )),
+        }
+    }
+}
+
+pub(crate) struct ResultRow {
+    pub(crate) rank: usize,
+    pub(crate) subjects: String,
+    pub(crate) total_hours: u16,
+    pub(crate) hours_per_day: String,
+    pub(crate) wait_hours: u16,
+}
+
+const HEADERS: [&str; 5] = [
+    "Rank",
+    "Subjects & Shifts",
+    "Total Hours",
+    "Hours/Day",
+    "Wait Hours",
+];
+
+fn row_cells(row: &ResultRow) -> [String; 5] {
+    [
+        row.rank.to_string(),
+        row.subjects.clone(),
+        row.total_hours.to_string(),
+        row.hours_per_day.clone(),
+        row.wait_hours.to_string(),
+    ]
+}
+
+pub(crate) fn render(rows: &[ResultRow], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Plain => render_plain(rows),
+        OutputFormat::Markdown => render_markdown(rows),
+        OutputFormat::Csv => render_csv(rows),
+    }
+}
+
+/// Collapses embedded newlines so a single cell can't split a table row
+/// across physical lines.
+fn strip_newlines(field: &str) -> String {
+    field.replace(['\n', '\r'], " ")
+}
+
+fn render_plain(rows: &[ResultRow]) -> String {
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(row_cells)
+        .map(|cell_row| cell_row.map(|cell| strip_newlines(&cell)))
+        .collect();
+
+    let mut widths = HEADERS.map(str::len);
+    for cell_row in &cells {
+        for (width, cell) in widths.iter_mut().zip(cell_row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[String; 5]| -> String {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .join(" | ")
+    };
+
+    let mut out = String::new();
+    out.push_str(&format_row(&HEADERS.map(str::to_string)));
+    out.push('\n');
+    out.push_str(&widths.iter().map(|width| "-".repeat(*width)).join("-+-"));
+    out.push('\n');
+    for cell_row in &cells {
+        out.push_str(&format_row(cell_row));
+        out.push('\n');
+    }
+    out
+}
+
+fn markdown_escape(field: &str) -> String {
+    strip_newlines(field).replace('|', "\\|")
+}
+
+fn render_markdown(rows: &[ResultRow]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "| {} |\n",
+        HEADERS.iter().map(|header| markdown_escape(header)).join(" | ")
+    ));
+    out.push_str(&format!(
+        "| {} |\n",
+        HEADERS.iter().map(|_| "---").join(" | ")
+    ));
+    for row in rows {
+        out.push_str(&format!(
+            "| {} |\n",
+            row_cells(row).iter().map(|cell| markdown_escape(cell)).join(" | ")
+        ));
+    }
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn render_csv(rows: &[ResultRow]) -> String {
+    let mut out = String::new();
+    out.push_str(&HEADERS.iter().map(|header| csv_escape(header)).join(","));
+    out.push('\n');
+    for row in rows {
+        out.push_str(&row_cells(row).iter().map(|cell| csv_escape(cell)).join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_with_subjects(subjects: &str) -> ResultRow {
+        ResultRow {
+            rank: 1,
+            subjects: subjects.to_string(),
+            total_hours: 10,
+            hours_per_day: "2".to_string(),
+            wait_hours: 1,
+        }
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_without_corrupting_the_table() {
+        let row = row_with_subjects("Math | Physics, \"Lab\"");
+        let out = render_markdown(&[row]);
+
+        let data_line = out.lines().nth(2).unwrap();
+        assert_eq!(data_line.matches(" | ").count(), 4);
+        assert!(data_line.contains("Math \\| Physics"));
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_commas_quotes_and_newlines() {
+        let row = row_with_subjects("Math | Physics, \"Lab\"\nLec1");
+        let out = render_csv(&[row]);
+
+        let expected_header = HEADERS.join(",");
+        let expected = format!("{expected_header}\n1,\"Math | Physics, \"\"Lab\"\"\nLec1\",10,2,1\n");
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn markdown_strips_embedded_newlines_without_splitting_the_row() {
+        let row = row_with_subjects("Biology\nLab Room 5");
+        let out = render_markdown(&[row]);
+
+        assert_eq!(out.lines().count(), 3);
+        assert!(out.contains("Biology Lab Room 5"));
+    }
+
+    #[test]
+    fn plain_strips_embedded_newlines_without_misaligning_columns() {
+        let row = row_with_subjects("Biology\nLab Room 5");
+        let out = render_plain(&[row]);
+
+        assert_eq!(out.lines().count(), 3);
+        assert!(out.lines().all(|line| !line.contains('\n')));
+        assert!(out.contains("Biology Lab Room 5"));
+    }
+}